@@ -0,0 +1,23 @@
+//! Constants used throughout the kernel
+
+/// The maximum number of syscalls that `TaskInfo`/`syscall_count` track
+pub const MAX_SYSCALL_NUM: usize = 500;
+
+/// Size of a single page in bytes
+pub const PAGE_SIZE: usize = 0x1000;
+
+/// The big stride value used by the stride scheduling algorithm.
+///
+/// Every runnable task accumulates `pass = BIG_STRIDE / priority` to its
+/// `stride` each time it is scheduled. Picking a large, fixed `BIG_STRIDE`
+/// keeps `pass` from rounding down to 0 for high-priority (large `priority`)
+/// tasks.
+pub const BIG_STRIDE: usize = 0xFFFF;
+
+/// Default stride-scheduling priority a freshly created (non-cloned) task
+/// starts with; `sys_set_priority` rejects anything below 2.
+pub const DEFAULT_PRIORITY: usize = 16;
+
+/// Size of the user stack `MemorySet::from_elf` lays out for a freshly
+/// `exec`'d task, one guard page above its highest `PT_LOAD` segment.
+pub const USER_STACK_SIZE: usize = 8 * PAGE_SIZE;
@@ -0,0 +1,23 @@
+//! Physical and virtual address/page-number types
+
+use crate::config::PAGE_SIZE;
+
+/// A virtual address
+#[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub struct VirtAddr(pub usize);
+
+impl From<usize> for VirtAddr {
+    fn from(v: usize) -> Self {
+        Self(v)
+    }
+}
+
+impl VirtAddr {
+    pub fn floor(&self) -> usize {
+        self.0 / PAGE_SIZE
+    }
+
+    pub fn ceil(&self) -> usize {
+        (self.0 + PAGE_SIZE - 1) / PAGE_SIZE
+    }
+}
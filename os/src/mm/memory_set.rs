@@ -0,0 +1,201 @@
+//! An address space (`MemorySet`) and its mapping permissions
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use xmas_elf::program::Type;
+use xmas_elf::ElfFile;
+
+use crate::config::{PAGE_SIZE, USER_STACK_SIZE};
+use crate::sync::UPSafeCell;
+
+use super::VirtAddr;
+
+bitflags::bitflags! {
+    /// Permission bits for a mapped page: the R/W/X permissions of the
+    /// user-facing `port` argument of `sys_mmap` (port bit0 = R, bit1 = W,
+    /// bit2 = X), stored one bit higher here (R = bit1, W = bit2, X = bit3),
+    /// plus the implicit `U` (user-accessible, bit4) bit every user mapping
+    /// carries.
+    pub struct MapPermission: u8 {
+        const R = 1 << 1;
+        const W = 1 << 2;
+        const X = 1 << 3;
+        const U = 1 << 4;
+    }
+}
+
+/// An error returned by `mmap_allocate_area`/`unmap_free_area` when the
+/// requested range overlaps an already (un)mapped area.
+pub struct MapAreaError;
+
+/// A `[start_vpn, end_vpn)` range `mmap_allocate_area` has recorded as
+/// mapped, so `unmap_free_area` (and later calls to `mmap_allocate_area`)
+/// can detect overlap without a real page table to walk.
+#[derive(Clone)]
+struct MapArea {
+    start_vpn: usize,
+    end_vpn: usize,
+    permission: MapPermission,
+}
+
+/// A task's address space
+pub struct MemorySet {
+    token: usize,
+    /// Shared behind an `Arc`/`UPSafeCell` rather than owned directly so
+    /// `clone_shared` can hand a `CLONE_VM` child a `MemorySet` that
+    /// genuinely sees the same mapped areas the parent does, not a
+    /// point-in-time snapshot of them (see `clone_shared` below).
+    areas: Arc<UPSafeCell<Vec<MapArea>>>,
+}
+
+impl MemorySet {
+    /// Allocate a fresh, empty address space.
+    ///
+    /// Real page tables are out of scope for this lab; the `token` is just a
+    /// unique handle, monotonically allocated the same way pids are, and
+    /// `areas` is bookkeeping `mmap_allocate_area`/`unmap_free_area` use to
+    /// reject overlapping ranges without a page table to consult.
+    pub fn new_bare() -> Self {
+        static NEXT_TOKEN: AtomicUsize = AtomicUsize::new(1);
+        Self {
+            token: NEXT_TOKEN.fetch_add(1, Ordering::Relaxed),
+            areas: Arc::new(unsafe { UPSafeCell::new(Vec::new()) }),
+        }
+    }
+
+    pub fn token(&self) -> usize {
+        self.token
+    }
+
+    /// Map `[start_va, end_va)` with the given permission.
+    ///
+    /// There is no real page table behind this lab's `MemorySet` (see
+    /// `new_bare`), so "mapping" just means recording the range in `areas`;
+    /// the invariant callers (`sys_mmap`, `from_elf` below) actually depend
+    /// on is rejecting a range that overlaps something already mapped,
+    /// which this still enforces instead of panicking.
+    pub fn mmap_allocate_area(
+        &mut self,
+        start_va: VirtAddr,
+        end_va: VirtAddr,
+        permission: MapPermission,
+    ) -> Result<(), MapAreaError> {
+        let start_vpn = start_va.floor();
+        let end_vpn = end_va.ceil();
+        let mut areas = self.areas.exclusive_access();
+        if areas
+            .iter()
+            .any(|a| start_vpn < a.end_vpn && a.start_vpn < end_vpn)
+        {
+            return Err(MapAreaError);
+        }
+        areas.push(MapArea {
+            start_vpn,
+            end_vpn,
+            permission,
+        });
+        Ok(())
+    }
+
+    /// Unmap `[start_va, end_va)`, which must already be entirely mapped.
+    pub fn unmap_free_area(
+        &mut self,
+        start_va: VirtAddr,
+        end_va: VirtAddr,
+    ) -> Result<(), MapAreaError> {
+        let start_vpn = start_va.floor();
+        let end_vpn = end_va.ceil();
+        let mut areas = self.areas.exclusive_access();
+        let covered: usize = areas
+            .iter()
+            .filter(|a| a.start_vpn >= start_vpn && a.end_vpn <= end_vpn)
+            .map(|a| a.end_vpn - a.start_vpn)
+            .sum();
+        if covered != end_vpn - start_vpn {
+            return Err(MapAreaError);
+        }
+        areas.retain(|a| !(a.start_vpn >= start_vpn && a.end_vpn <= end_vpn));
+        Ok(())
+    }
+
+    /// Share this address space with a `CLONE_VM` child: same `areas`
+    /// behind the same `Arc`, so a `sys_mmap`/`sys_munmap` either task
+    /// makes is immediately visible to the other, not just a snapshot
+    /// taken at `clone()` time.
+    pub fn clone_shared(&self) -> Self {
+        Self {
+            token: self.token,
+            areas: Arc::clone(&self.areas),
+        }
+    }
+
+    /// Deep-copy this address space for a plain `fork`-style child: a new,
+    /// independent address space with the same mapped areas as `self` at
+    /// the time of the copy.
+    ///
+    /// Copying the backing page table frame-by-frame is out of scope for
+    /// this lab (there is no frame allocator behind `MemorySet` to copy —
+    /// see `mmap_allocate_area`/`unmap_free_area` above), but the child
+    /// must still get its own independent `areas`/token rather than sharing
+    /// the parent's, which is what actually distinguishes a deep-copied
+    /// fork child from a `CLONE_VM` one in `clone_shared` above.
+    pub fn deep_copy(&self) -> Self {
+        let copy = Self::new_bare();
+        *copy.areas.exclusive_access() = self.areas.exclusive_access().clone();
+        copy
+    }
+
+    /// Parse `elf_data`'s program headers and record each `PT_LOAD` segment
+    /// as a mapped area (via `mmap_allocate_area` above), then lay out a
+    /// user stack one guard page above the highest segment.
+    ///
+    /// Returns the new `MemorySet` together with the user stack pointer and
+    /// entry point `exec` needs to seed a fresh `TrapContext`, or `None` if
+    /// `elf_data` isn't a parseable ELF image (e.g. `spawn` against a
+    /// missing app).
+    pub fn from_elf(elf_data: &[u8]) -> Option<(Self, usize, usize)> {
+        let elf = ElfFile::new(elf_data).ok()?;
+        let entry_point = elf.header.pt2.entry_point() as usize;
+
+        let mut memory_set = Self::new_bare();
+        let mut max_end_vpn = 0;
+        for ph in elf.program_iter() {
+            if ph.get_type() != Ok(Type::Load) {
+                continue;
+            }
+            let start_va = VirtAddr::from(ph.virtual_addr() as usize);
+            let end_va = VirtAddr::from((ph.virtual_addr() + ph.mem_size()) as usize);
+            let flags = ph.flags();
+            let mut permission = MapPermission::U;
+            if flags.is_read() {
+                permission |= MapPermission::R;
+            }
+            if flags.is_write() {
+                permission |= MapPermission::W;
+            }
+            if flags.is_execute() {
+                permission |= MapPermission::X;
+            }
+            memory_set
+                .mmap_allocate_area(start_va, end_va, permission)
+                .ok()?;
+            max_end_vpn = max_end_vpn.max(end_va.ceil());
+        }
+
+        // One guard page below the user stack, matching this lab's other
+        // page-granular layout decisions.
+        let user_stack_bottom = (max_end_vpn + 1) * PAGE_SIZE;
+        let user_stack_top = user_stack_bottom + USER_STACK_SIZE;
+        memory_set
+            .mmap_allocate_area(
+                VirtAddr::from(user_stack_bottom),
+                VirtAddr::from(user_stack_top),
+                MapPermission::R | MapPermission::W | MapPermission::U,
+            )
+            .ok()?;
+
+        Some((memory_set, user_stack_top, entry_point))
+    }
+}
@@ -0,0 +1,11 @@
+//! Memory management: address spaces, page tables, user-pointer translation
+
+mod address;
+mod memory_set;
+mod page_table;
+
+pub use address::VirtAddr;
+pub use memory_set::{MapPermission, MemorySet};
+pub use page_table::{
+    translated_byte_buffer, translated_copyout, translated_refmut, translated_str,
+};
@@ -0,0 +1,84 @@
+//! Translation of user-space pointers into kernel-accessible references
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem::size_of;
+use core::slice;
+
+use crate::config::PAGE_SIZE;
+
+/// Return a kernel-accessible reference to `ptr` in `token`'s address space.
+/// There is no real page table in this lab, so `ptr` is used as-is
+/// (identity-mapped) and `token` is not consulted.
+///
+/// This assumes the value pointed to by `ptr` does not straddle a page
+/// boundary; callers whose struct might span two non-contiguous physical
+/// pages must use [`translated_byte_buffer`] (or [`translated_copyout`])
+/// instead.
+pub fn translated_refmut<T>(_token: usize, ptr: *mut T) -> &'static mut T {
+    unsafe { &mut *ptr }
+}
+
+/// Read a NUL-terminated string out of user space at `ptr`.
+pub fn translated_str(_token: usize, ptr: *const u8) -> String {
+    let mut s = String::new();
+    let mut p = ptr;
+    loop {
+        let ch = unsafe { *p };
+        if ch == 0 {
+            break;
+        }
+        s.push(ch as char);
+        p = unsafe { p.add(1) };
+    }
+    s
+}
+
+/// Split `[buf, buf + len)` in user space into a sequence of kernel byte
+/// slices, one per page fragment it touches, so that callers can copy
+/// into/out of a buffer that straddles non-contiguous pages.
+///
+/// There is no real page table behind `_token` in this lab (see
+/// [`translated_refmut`]'s identity-mapped assumption), so this can't walk
+/// actual page table entries to find where physical frames stop being
+/// contiguous. It still has to honor the one invariant that matters to
+/// callers, though: never hand back a fragment that crosses a page
+/// boundary. So it splits `[buf, buf + len)` at each `PAGE_SIZE` boundary it
+/// crosses, exactly as a page-table-backed implementation would — each
+/// fragment below is guaranteed to lie within a single page.
+pub fn translated_byte_buffer(_token: usize, buf: *const u8, len: usize) -> Vec<&'static mut [u8]> {
+    let mut fragments = Vec::new();
+    let start = buf as usize;
+    let end = start + len;
+    let mut cur = start;
+    while cur < end {
+        let page_end = (cur / PAGE_SIZE + 1) * PAGE_SIZE;
+        let fragment_end = page_end.min(end);
+        fragments.push(unsafe {
+            slice::from_raw_parts_mut(cur as *mut u8, fragment_end - cur)
+        });
+        cur = fragment_end;
+    }
+    fragments
+}
+
+/// Copy `*src` out to the user-space pointer `dst`, safely handling the case
+/// where `size_of::<T>()` bytes starting at `dst` straddle two (or more)
+/// non-contiguous physical pages.
+///
+/// `translated_refmut` resolves `dst` to a *single* contiguous kernel
+/// pointer, so a caller whose struct (e.g. `TimeVal`/`TaskInfo`) happens to
+/// cross a page boundary would silently write past the first page's mapped
+/// frame. This instead serializes `*src` into bytes and walks
+/// `translated_byte_buffer` over `[dst, dst + size_of::<T>())`, copying into
+/// each page fragment it returns.
+pub fn translated_copyout<T>(token: usize, dst: *mut T, src: &T) {
+    let len = size_of::<T>();
+    let src_bytes = unsafe { slice::from_raw_parts(src as *const T as *const u8, len) };
+    let mut offset = 0;
+    for fragment in translated_byte_buffer(token, dst as *const u8, len) {
+        let end = offset + fragment.len();
+        fragment.copy_from_slice(&src_bytes[offset..end]);
+        offset = end;
+    }
+}
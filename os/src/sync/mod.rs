@@ -0,0 +1,5 @@
+//! Synchronization primitives used across the kernel
+
+mod up;
+
+pub use up::UPSafeCell;
@@ -0,0 +1,82 @@
+//! Syscall dispatch
+
+mod process;
+
+pub use process::*;
+
+use crate::config::MAX_SYSCALL_NUM;
+use crate::task::{
+    current_task, exit_current_and_run_next, stop_current_and_run_next, SeccompAction,
+};
+use crate::timer::get_time_us;
+
+/// `EPERM`, returned (negated) when a seccomp filter denies a syscall
+const EPERM: isize = 1;
+/// Exit code a task receives when its own seccomp filter kills it
+const SECCOMP_KILL_EXIT_CODE: i32 = -159;
+
+const SYSCALL_GETPID: usize = 172;
+const SYSCALL_CLONE: usize = 220;
+const SYSCALL_EXEC: usize = 221;
+const SYSCALL_WAITPID: usize = 260;
+const SYSCALL_EXIT: usize = 93;
+const SYSCALL_YIELD: usize = 124;
+const SYSCALL_GET_TIME: usize = 169;
+const SYSCALL_SET_PRIORITY: usize = 140;
+const SYSCALL_MMAP: usize = 222;
+const SYSCALL_MUNMAP: usize = 215;
+const SYSCALL_SBRK: usize = 214;
+const SYSCALL_SPAWN: usize = 400;
+const SYSCALL_TASK_INFO: usize = 410;
+const SYSCALL_SECCOMP: usize = 277;
+const SYSCALL_PTRACE: usize = 117;
+const SYSCALL_SYSCALL_STATS: usize = 420;
+
+/// Dispatch a trapped syscall to its handler.
+///
+/// A task being traced parks itself here, on every syscall entry, until its
+/// tracer `PTRACE_CONT`s it back to running — mirroring `PTRACE_SYSCALL`-style
+/// tracing. Once past that, the calling task's seccomp filter is consulted
+/// before dispatch. The time spent in the handler is sampled around it and
+/// folded into the calling task's per-syscall `syscall_count`/
+/// `syscall_time_us`, exposed via `sys_task_info`/`sys_syscall_stats`.
+pub fn syscall(syscall_id: usize, args: [usize; 4]) -> isize {
+    let task = current_task().unwrap();
+    if task.inner_exclusive_access().tracer.is_some() {
+        stop_current_and_run_next();
+    }
+
+    match task.inner_exclusive_access().seccomp.action_for(syscall_id) {
+        SeccompAction::Allow => {}
+        SeccompAction::Deny => return -EPERM,
+        SeccompAction::Kill => exit_current_and_run_next(SECCOMP_KILL_EXIT_CODE),
+    }
+
+    let entry_us = get_time_us();
+    let ret = match syscall_id {
+        SYSCALL_GETPID => sys_getpid(),
+        SYSCALL_CLONE => sys_clone(args[0], args[1]),
+        SYSCALL_EXEC => sys_exec(args[0] as *const u8),
+        SYSCALL_WAITPID => sys_waitpid(args[0] as isize, args[1] as *mut i32),
+        SYSCALL_EXIT => sys_exit(args[0] as i32),
+        SYSCALL_YIELD => sys_yield(),
+        SYSCALL_GET_TIME => sys_get_time(args[0] as *mut TimeVal, args[1]),
+        SYSCALL_SET_PRIORITY => sys_set_priority(args[0] as isize),
+        SYSCALL_MMAP => sys_mmap(args[0], args[1], args[2]),
+        SYSCALL_MUNMAP => sys_munmap(args[0], args[1]),
+        SYSCALL_SBRK => sys_sbrk(args[0] as i32),
+        SYSCALL_SPAWN => sys_spawn(args[0] as *const u8),
+        SYSCALL_TASK_INFO => sys_task_info(args[0] as *mut TaskInfo),
+        SYSCALL_SECCOMP => sys_seccomp(args[0], args[1], args[2]),
+        SYSCALL_PTRACE => sys_ptrace(args[0], args[1], args[2], args[3]),
+        SYSCALL_SYSCALL_STATS => sys_syscall_stats(args[0] as *mut SyscallStats),
+        _ => panic!("Unsupported syscall_id: {}", syscall_id),
+    };
+
+    if syscall_id < MAX_SYSCALL_NUM {
+        let mut inner = task.inner_exclusive_access();
+        inner.syscall_count[syscall_id] += 1;
+        inner.syscall_time_us[syscall_id] += get_time_us() - entry_us;
+    }
+    ret
+}
@@ -1,15 +1,18 @@
 //! Process management syscalls
-use alloc::sync::Arc;
+use alloc::sync::{Arc, Weak};
 
 use crate::{
     config::MAX_SYSCALL_NUM,
     loader::get_app_data_by_name,
-    mm::{translated_refmut, translated_str},
+    mm::{translated_copyout, translated_refmut, translated_str},
     task::{
-        add_task, current_task, current_user_token, exit_current_and_run_next,
-        suspend_current_and_run_next, TaskStatus,
+        add_task, current_task, current_user_token, exit_current_and_run_next, pid_to_task,
+        suspend_current_and_run_next, SeccompAction, StopState, TaskControlBlock,
+        TaskControlBlockInner, TaskStatus, PTRACE_ATTACH, PTRACE_CONT, PTRACE_GETREGS,
+        PTRACE_PEEKDATA, PTRACE_POKEDATA, PTRACE_SETREGS, PTRACE_TRACEME,
     },
     mm::{VirtAddr},
+    trap::TrapContext,
 };
 use crate::config::PAGE_SIZE;
 use crate::mm::MapPermission;
@@ -33,6 +36,17 @@ pub struct TaskInfo {
     time: usize,
 }
 
+/// Per-syscall call counts paired with cumulative elapsed microseconds,
+/// filled in by `sys_syscall_stats` so user space can profile which
+/// syscalls dominate a program's runtime.
+#[repr(C)]
+pub struct SyscallStats {
+    /// per-syscall call counts
+    pub syscall_times: [u32; MAX_SYSCALL_NUM],
+    /// per-syscall cumulative elapsed microseconds
+    pub syscall_time_us: [usize; MAX_SYSCALL_NUM],
+}
+
 /// task exits and submit an exit code
 pub fn sys_exit(exit_code: i32) -> ! {
     trace!("kernel:pid[{}] sys_exit", current_task().unwrap().pid.0);
@@ -52,15 +66,30 @@ pub fn sys_getpid() -> isize {
     current_task().unwrap().pid.0 as isize
 }
 
+/// Legacy `fork`, kept for existing callers: equivalent to `sys_clone(0, 0)`.
 pub fn sys_fork() -> isize {
-    trace!("kernel:pid[{}] sys_fork", current_task().unwrap().pid.0);
+    sys_clone(0, 0)
+}
+
+/// Generalized `fork`: create a child per `clone()` `flags`.
+///
+/// `CLONE_VM` makes the child share this task's address space instead of
+/// deep-copying it (thread-style clone); a non-zero `stack` overrides the
+/// child's user `sp` so a thread library can supply its own stack.
+pub fn sys_clone(flags: usize, stack: usize) -> isize {
+    trace!(
+        "kernel:pid[{}] sys_clone flags={:#x} stack={:#x}",
+        current_task().unwrap().pid.0,
+        flags,
+        stack
+    );
     let current_task = current_task().unwrap();
-    let new_task = current_task.fork();
+    let new_task = current_task.clone_task(flags, stack);
     let new_pid = new_task.pid.0;
     // modify trap context of new_task, because it returns immediately after switching
     let trap_cx = new_task.inner_exclusive_access().get_trap_cx();
     // we do not have to move to next instruction since we have done it before
-    // for child process, fork returns 0
+    // for child process, fork/clone returns 0
     trap_cx.x[10] = 0;
     // add new task to scheduler
     add_task(new_task);
@@ -82,11 +111,28 @@ pub fn sys_exec(path: *const u8) -> isize {
 
 /// If there is not a child process whose pid is same as given, return -1.
 /// Else if there is a child process but it is still running, return -2.
+/// If `pid` names a task this task is tracing and that task is parked at a
+/// ptrace stop, return -3 instead: the tracer should inspect/modify it via
+/// `sys_ptrace` and resume it with `PTRACE_CONT`.
 pub fn sys_waitpid(pid: isize, exit_code_ptr: *mut i32) -> isize {
     trace!("kernel::pid[{}] sys_waitpid [{}]", current_task().unwrap().pid.0, pid);
     let task = current_task().unwrap();
     // find a child process
 
+    if pid > 0 {
+        if let Some(tracee) = pid_to_task(pid as usize) {
+            let tracee_inner = tracee.inner_exclusive_access();
+            let traced_by_us = tracee_inner
+                .tracer
+                .as_ref()
+                .and_then(Weak::upgrade)
+                .is_some_and(|t| Arc::ptr_eq(&t, &task));
+            if traced_by_us && tracee_inner.stop_state == StopState::Stopped {
+                return -3;
+            }
+        }
+    }
+
     // ---- access current PCB exclusively
     let mut inner = task.inner_exclusive_access();
     if !inner
@@ -119,37 +165,68 @@ pub fn sys_waitpid(pid: isize, exit_code_ptr: *mut i32) -> isize {
     // ---- release current PCB automatically
 }
 
-/// YOUR JOB: get time with second and microsecond
-/// HINT: You might reimplement it with virtual memory management.
-/// HINT: What if [`TimeVal`] is splitted by two pages ?
+/// get time with second and microsecond
+///
+/// Built on the kernel stack and copied out with [`translated_copyout`] so a
+/// `TimeVal` that straddles two non-contiguous physical pages is still
+/// written correctly.
 pub fn sys_get_time(ts: *mut TimeVal, _tz: usize) -> isize {
     let token = current_user_token();
-    let ts = translated_refmut(token, ts);
 
     let us = get_time_us();
-    (*ts).sec = us / 1_000_000;
-    (*ts).usec = us % 1_000_000;
+    let kernel_ts = TimeVal {
+        sec: us / 1_000_000,
+        usec: us % 1_000_000,
+    };
+    translated_copyout(token, ts, &kernel_ts);
     0
 }
 
-/// YOUR JOB: Finish sys_task_info to pass testcases
-/// HINT: You might reimplement it with virtual memory management.
-/// HINT: What if [`TaskInfo`] is splitted by two pages ?
+/// Fill in and return the current task's [`TaskInfo`].
+///
+/// Built on the kernel stack and copied out with [`translated_copyout`] so a
+/// `TaskInfo` that straddles two non-contiguous physical pages is still
+/// written correctly.
 pub fn sys_task_info(ti: *mut TaskInfo) -> isize {
     let token = current_user_token();
-    let ti = translated_refmut(token, ti);
 
     let curr_task = current_task().unwrap();
     let inner = curr_task.inner_exclusive_access();
 
-    (*ti).status = inner.task_status;
-    (*ti).syscall_times = inner.syscall_count;
-
     let us = get_time_us();
     let sec = us / 1_000_000;
     let usec = us % 1_000_000;
     let t = (sec & 0xffff) * 1000 + usec / 1000;
-    (*ti).time = t - inner.running_time;
+
+    let kernel_ti = TaskInfo {
+        status: inner.task_status,
+        syscall_times: inner.syscall_count,
+        time: t - inner.running_time,
+    };
+    drop(inner);
+    translated_copyout(token, ti, &kernel_ti);
+    0
+}
+
+/// Fill in and return the current task's per-syscall call counts and
+/// cumulative elapsed microseconds, sampled around dispatch in
+/// [`crate::syscall::syscall`].
+///
+/// Built on the kernel stack and copied out with [`translated_copyout`] so
+/// a `SyscallStats` that straddles two non-contiguous physical pages is
+/// still written correctly.
+pub fn sys_syscall_stats(buf: *mut SyscallStats) -> isize {
+    let token = current_user_token();
+
+    let curr_task = current_task().unwrap();
+    let inner = curr_task.inner_exclusive_access();
+    let stats = SyscallStats {
+        syscall_times: inner.syscall_count,
+        syscall_time_us: inner.syscall_time_us,
+    };
+    drop(inner);
+
+    translated_copyout(token, buf, &stats);
     0
 }
 
@@ -235,7 +312,7 @@ pub fn sys_spawn(path: *const u8) -> isize {
 
     let current_task = current_task().unwrap();
 
-    let new_task = current_task.spawn(path);
+    let new_task = current_task.spawn(&path);
     let new_pid = new_task.pid.0;
 
     // add new task to scheduler
@@ -244,11 +321,159 @@ pub fn sys_spawn(path: *const u8) -> isize {
     new_pid as isize
 }
 
-// YOUR JOB: Set task priority.
-pub fn sys_set_priority(_prio: isize) -> isize {
+/// Set the current task's stride-scheduling priority.
+///
+/// Rejects `prio < 2`, returning -1, which keeps `pass = BIG_STRIDE / priority`
+/// at most `BIG_STRIDE / 2`. On success returns the accepted priority.
+pub fn sys_set_priority(prio: isize) -> isize {
     trace!(
-        "kernel:pid[{}] sys_set_priority NOT IMPLEMENTED",
-        current_task().unwrap().pid.0
+        "kernel:pid[{}] sys_set_priority {}",
+        current_task().unwrap().pid.0,
+        prio
     );
-    -1
+    if prio < 2 {
+        return -1;
+    }
+    let curr_task = current_task().unwrap();
+    curr_task.inner_exclusive_access().priority = prio as usize;
+    prio
+}
+
+/// Only mode supported by this lab's `sys_seccomp`: install/tighten a single
+/// syscall rule on the calling task's filter.
+const SECCOMP_SET_MODE_FILTER: usize = 1;
+
+/// Install a seccomp rule for `syscall_nr` on the calling task, so it can
+/// voluntarily drop its own privileges (e.g. right before `exec`ing
+/// untrusted code). Rules are monotonic: a syscall already `Deny`/`Kill`ed
+/// cannot be set back to `Allow`.
+///
+/// Returns 0 on success, -1 if `mode` or `action` is unsupported,
+/// `syscall_nr` is out of range, or the rule would loosen an existing one.
+pub fn sys_seccomp(mode: usize, syscall_nr: usize, action: usize) -> isize {
+    if mode != SECCOMP_SET_MODE_FILTER {
+        return -1;
+    }
+    let action = match action {
+        0 => SeccompAction::Allow,
+        1 => SeccompAction::Deny,
+        2 => SeccompAction::Kill,
+        _ => return -1,
+    };
+
+    let curr_task = current_task().unwrap();
+    match curr_task
+        .inner_exclusive_access()
+        .seccomp
+        .set(syscall_nr, action)
+    {
+        Ok(()) => 0,
+        Err(()) => -1,
+    }
+}
+
+/// Attach to, continue, or inspect/modify another task for debugging,
+/// modeled on the attach/continue/peek/poke flow in the Starnix task layer.
+///
+/// `request` is one of the `PTRACE_*` constants; `pid` identifies the
+/// tracee and is ignored for `PTRACE_TRACEME`; `addr` is a word address in
+/// the tracee's address space for `PEEKDATA`/`POKEDATA`; `data` is either
+/// the word value to write (`POKEDATA`) or a pointer in the *caller's*
+/// address space to read the result into / read the new value from
+/// (`PEEKDATA`, `GETREGS`, `SETREGS`).
+///
+/// Returns 0 on success, -1 if the request is unsupported, `pid` names no
+/// live task, the caller isn't that task's tracer, or (for `PTRACE_CONT`)
+/// the tracee isn't stopped.
+pub fn sys_ptrace(request: usize, pid: usize, addr: usize, data: usize) -> isize {
+    let current = current_task().unwrap();
+
+    match request {
+        PTRACE_TRACEME => {
+            let mut inner = current.inner_exclusive_access();
+            inner.tracer = inner.parent.clone();
+            0
+        }
+        PTRACE_ATTACH => {
+            let Some(tracee) = pid_to_task(pid) else {
+                return -1;
+            };
+            tracee.inner_exclusive_access().tracer = Some(Arc::downgrade(&current));
+            0
+        }
+        PTRACE_CONT => {
+            let tracee = match tracee_of(pid, &current) {
+                Ok(tracee) => tracee,
+                Err(err) => return err,
+            };
+            let mut tracee_inner = tracee.inner_exclusive_access();
+            if tracee_inner.stop_state != StopState::Stopped {
+                return -1;
+            }
+            tracee_inner.stop_state = StopState::Running;
+            drop(tracee_inner);
+            add_task(tracee);
+            0
+        }
+        PTRACE_PEEKDATA => {
+            let tracee = match tracee_of(pid, &current) {
+                Ok(tracee) => tracee,
+                Err(err) => return err,
+            };
+            let tracee_token = tracee.inner_exclusive_access().get_user_token();
+            let word = *translated_refmut(tracee_token, addr as *mut usize);
+            *translated_refmut(current_user_token(), data as *mut usize) = word;
+            0
+        }
+        PTRACE_POKEDATA => {
+            let tracee = match tracee_of(pid, &current) {
+                Ok(tracee) => tracee,
+                Err(err) => return err,
+            };
+            let tracee_token = tracee.inner_exclusive_access().get_user_token();
+            *translated_refmut(tracee_token, addr as *mut usize) = data;
+            0
+        }
+        PTRACE_GETREGS => {
+            let tracee = match tracee_of(pid, &current) {
+                Ok(tracee) => tracee,
+                Err(err) => return err,
+            };
+            let trap_cx = *tracee.inner_exclusive_access().get_trap_cx();
+            translated_copyout(current_user_token(), data as *mut TrapContext, &trap_cx);
+            0
+        }
+        PTRACE_SETREGS => {
+            let tracee = match tracee_of(pid, &current) {
+                Ok(tracee) => tracee,
+                Err(err) => return err,
+            };
+            let new_cx = *translated_refmut(current_user_token(), data as *mut TrapContext);
+            *tracee.inner_exclusive_access().get_trap_cx() = new_cx;
+            0
+        }
+        _ => -1,
+    }
+}
+
+/// Look up `pid`'s task and confirm `tracer` is already recorded as its
+/// tracer, collapsing the "no such task" / "not its tracer" failure cases
+/// every `PTRACE_*` request but `TRACEME`/`ATTACH` shares into the one `-1`
+/// `sys_ptrace` returns on error.
+fn tracee_of(pid: usize, tracer: &Arc<TaskControlBlock>) -> Result<Arc<TaskControlBlock>, isize> {
+    let tracee = pid_to_task(pid).ok_or(-1)?;
+    if is_tracer_of(&tracee.inner_exclusive_access(), tracer) {
+        Ok(tracee)
+    } else {
+        Err(-1)
+    }
+}
+
+/// Whether `tracer` is the tracer currently recorded on `tracee`
+fn is_tracer_of(tracee: &TaskControlBlockInner, tracer: &Arc<TaskControlBlock>) -> bool {
+    tracee
+        .tracer
+        .as_ref()
+        .and_then(Weak::upgrade)
+        .is_some_and(|t| Arc::ptr_eq(&t, tracer))
 }
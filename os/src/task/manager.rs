@@ -0,0 +1,97 @@
+//! Implementation of [`TaskManager`]
+//!
+//! The ready queue used to be a plain FIFO `VecDeque`. It is now a
+//! `BinaryHeap` keyed on stride so that `fetch` can pull out the runnable
+//! task with the smallest stride in `O(log n)` instead of scanning linearly.
+
+use alloc::collections::BinaryHeap;
+use alloc::sync::Arc;
+use core::cmp::Ordering;
+
+use super::TaskControlBlock;
+use crate::sync::UPSafeCell;
+use lazy_static::lazy_static;
+
+/// A heap entry wrapping a task and ordering it by stride.
+///
+/// `stride` can wrap around `usize`, so instead of comparing the raw values
+/// we compare `a.stride.wrapping_sub(b.stride)` as a signed value: since
+/// `pass <= BIG_STRIDE / 2`, a task whose stride just wrapped past
+/// `usize::MAX` still compares as larger than every task that hasn't, so it
+/// is not picked ahead of them, which is exactly what stride scheduling needs.
+struct StrideEntry(Arc<TaskControlBlock>);
+
+impl StrideEntry {
+    fn stride(&self) -> usize {
+        self.0.inner_exclusive_access().stride
+    }
+}
+
+impl PartialEq for StrideEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.stride() == other.stride()
+    }
+}
+impl Eq for StrideEntry {}
+
+impl PartialOrd for StrideEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for StrideEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // `BinaryHeap` is a max-heap; reverse the wrapping comparison so the
+        // *smallest* stride sorts to the top.
+        let diff = other.stride().wrapping_sub(self.stride()) as isize;
+        diff.cmp(&0)
+    }
+}
+
+/// A heap-backed ready queue, always able to hand out the task with the
+/// smallest stride in logarithmic time.
+pub struct TaskManager {
+    ready_queue: BinaryHeap<StrideEntry>,
+}
+
+/// A simple FIFO scheduler would use a `VecDeque`; stride scheduling instead
+/// needs a min-heap on stride, implemented here via a reversed-ordering
+/// `BinaryHeap`.
+impl TaskManager {
+    pub fn new() -> Self {
+        Self {
+            ready_queue: BinaryHeap::new(),
+        }
+    }
+
+    pub fn add(&mut self, task: Arc<TaskControlBlock>) {
+        self.ready_queue.push(StrideEntry(task));
+    }
+
+    /// Pop the runnable task with the smallest stride and charge it its pass.
+    pub fn fetch(&mut self) -> Option<Arc<TaskControlBlock>> {
+        let entry = self.ready_queue.pop()?;
+        let task = entry.0;
+        let mut inner = task.inner_exclusive_access();
+        let pass = inner.pass();
+        inner.stride = inner.stride.wrapping_add(pass);
+        drop(inner);
+        Some(task)
+    }
+}
+
+lazy_static! {
+    pub static ref TASK_MANAGER: UPSafeCell<TaskManager> =
+        unsafe { UPSafeCell::new(TaskManager::new()) };
+}
+
+/// Add a task to the stride-ordered ready queue
+pub fn add_task(task: Arc<TaskControlBlock>) {
+    TASK_MANAGER.exclusive_access().add(task);
+}
+
+/// Pop the task with the smallest stride from the ready queue
+pub fn fetch_task() -> Option<Arc<TaskControlBlock>> {
+    TASK_MANAGER.exclusive_access().fetch()
+}
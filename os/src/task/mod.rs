@@ -0,0 +1,76 @@
+//! Task management: the task control block, the stride-scheduled ready
+//! queue, and the current-task-on-this-core bookkeeping.
+
+mod context;
+mod manager;
+mod pid;
+mod processor;
+mod ptrace;
+mod seccomp;
+mod switch;
+mod task;
+
+pub use context::TaskContext;
+pub use manager::add_task;
+pub use pid::pid_to_task;
+pub use processor::{
+    current_task, current_trap_cx, current_user_token, run_tasks, schedule,
+};
+pub use ptrace::{
+    StopState, PTRACE_ATTACH, PTRACE_CONT, PTRACE_GETREGS, PTRACE_PEEKDATA, PTRACE_POKEDATA,
+    PTRACE_SETREGS, PTRACE_TRACEME,
+};
+pub use seccomp::{SeccompAction, SeccompFilter};
+pub use task::{TaskControlBlock, TaskControlBlockInner, TaskStatus, CLONE_THREAD, CLONE_VM};
+
+/// Suspend the current task, put it back on the ready queue, and run the
+/// next one.
+pub fn suspend_current_and_run_next() {
+    let task = processor::PROCESSOR
+        .exclusive_access()
+        .take_current()
+        .expect("suspend called with no current task");
+
+    let mut inner = task.inner_exclusive_access();
+    let task_cx_ptr = &mut inner.task_cx as *mut TaskContext;
+    inner.task_status = TaskStatus::Ready;
+    drop(inner);
+
+    add_task(task);
+    schedule(task_cx_ptr);
+}
+
+/// Park the current task as ptrace-stopped and run the next one, mirroring
+/// [`suspend_current_and_run_next`] except the parked task is *not*
+/// re-added to the ready queue — only its tracer's `PTRACE_CONT` does that,
+/// via [`add_task`] in `sys_ptrace`.
+pub fn stop_current_and_run_next() {
+    let task = processor::PROCESSOR
+        .exclusive_access()
+        .take_current()
+        .expect("stop called with no current task");
+
+    let mut inner = task.inner_exclusive_access();
+    let task_cx_ptr = &mut inner.task_cx as *mut TaskContext;
+    inner.stop_state = StopState::Stopped;
+    drop(inner);
+
+    schedule(task_cx_ptr);
+}
+
+/// Exit the current task, recording its exit code, and run the next one.
+pub fn exit_current_and_run_next(exit_code: i32) {
+    let task = processor::PROCESSOR
+        .exclusive_access()
+        .take_current()
+        .expect("exit called with no current task");
+
+    let mut inner = task.inner_exclusive_access();
+    inner.task_status = TaskStatus::Zombie;
+    inner.exit_code = exit_code;
+    drop(inner);
+    // The zombie's task context is never switched back into, so any
+    // context is fine here.
+    let mut unused = TaskContext::zero_init();
+    schedule(&mut unused as *mut TaskContext);
+}
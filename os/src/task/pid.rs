@@ -0,0 +1,57 @@
+//! Allocator for process identifiers and kernel stacks
+
+use alloc::collections::BTreeMap;
+use alloc::sync::{Arc, Weak};
+
+use crate::sync::UPSafeCell;
+use lazy_static::lazy_static;
+
+use super::TaskControlBlock;
+
+/// A handle to an allocated pid. Pids come from a monotonic counter and are
+/// not released or reused when the handle is dropped.
+pub struct PidHandle(pub usize);
+
+/// A task's kernel stack, used for its trap/exception handling
+pub struct KernelStack {
+    pid: usize,
+}
+
+/// Allocate a fresh pid
+pub fn pid_alloc() -> PidHandle {
+    // Allocation strategy is out of scope for this lab; callers only rely on
+    // the handle being unique and stable for the task's lifetime.
+    static NEXT_PID: core::sync::atomic::AtomicUsize = core::sync::atomic::AtomicUsize::new(0);
+    PidHandle(NEXT_PID.fetch_add(1, core::sync::atomic::Ordering::Relaxed))
+}
+
+impl KernelStack {
+    /// Create the kernel stack handle for the given pid. No stack memory is
+    /// allocated behind it; `get_top` returns 0.
+    pub fn new(pid_handle: &PidHandle) -> Self {
+        Self { pid: pid_handle.0 }
+    }
+
+    pub fn get_top(&self) -> usize {
+        let _ = self.pid;
+        0
+    }
+}
+
+lazy_static! {
+    /// pid -> task lookup, so `PTRACE_ATTACH` can find a tracee by its bare
+    /// pid instead of needing a handle to it.
+    static ref PID_TABLE: UPSafeCell<BTreeMap<usize, Weak<TaskControlBlock>>> =
+        unsafe { UPSafeCell::new(BTreeMap::new()) };
+}
+
+/// Register a freshly created task under its pid for later lookup by
+/// [`pid_to_task`].
+pub fn register_task(pid: usize, task: &Arc<TaskControlBlock>) {
+    PID_TABLE.exclusive_access().insert(pid, Arc::downgrade(task));
+}
+
+/// Look up a still-alive task by pid.
+pub fn pid_to_task(pid: usize) -> Option<Arc<TaskControlBlock>> {
+    PID_TABLE.exclusive_access().get(&pid).and_then(Weak::upgrade)
+}
@@ -0,0 +1,32 @@
+//! A minimal ptrace subsystem, modeled on the attach/continue/peek/poke flow
+//! in the Starnix task layer: a tracee is parked as [`StopState::Stopped`]
+//! the next time it traps, and its tracer's `sys_waitpid` observes the stop
+//! so it can inspect or rewrite the tracee's saved `TrapContext` before
+//! resuming it with `PTRACE_CONT`.
+
+/// A traced task's stop/run state, independent of [`super::TaskStatus`] — a
+/// task can be `Ready`/`Running` in the scheduler's eyes and still be
+/// "stopped for its tracer" in the ptrace sense.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum StopState {
+    /// Not traced, or traced but currently allowed to run
+    Running,
+    /// Parked at a trap, waiting for its tracer to `PTRACE_CONT` it
+    Stopped,
+}
+
+/// `PTRACE_TRACEME`: the calling task marks itself traced by its parent and
+/// stops the next time it traps.
+pub const PTRACE_TRACEME: usize = 0;
+/// `PTRACE_PEEKDATA`: read one word from the tracee's address space
+pub const PTRACE_PEEKDATA: usize = 1;
+/// `PTRACE_POKEDATA`: write one word into the tracee's address space
+pub const PTRACE_POKEDATA: usize = 2;
+/// `PTRACE_CONT`: resume a stopped tracee
+pub const PTRACE_CONT: usize = 3;
+/// `PTRACE_ATTACH`: become the tracer of an already-running task by pid
+pub const PTRACE_ATTACH: usize = 4;
+/// `PTRACE_GETREGS`: read the tracee's saved `TrapContext`
+pub const PTRACE_GETREGS: usize = 5;
+/// `PTRACE_SETREGS`: overwrite the tracee's saved `TrapContext`
+pub const PTRACE_SETREGS: usize = 6;
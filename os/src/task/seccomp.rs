@@ -0,0 +1,66 @@
+//! A per-task seccomp-style syscall filter, letting a process voluntarily
+//! drop its own privileges before running untrusted code (e.g. after a
+//! `clone`/`fork` but before `exec`ing an attacker-supplied binary).
+
+use alloc::collections::BTreeMap;
+
+use crate::config::MAX_SYSCALL_NUM;
+
+/// What to do with a syscall a filter matches
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SeccompAction {
+    /// Dispatch the syscall normally
+    Allow,
+    /// Refuse the syscall and return `-EPERM` to the caller
+    Deny,
+    /// Kill the calling task instead of letting the syscall run
+    Kill,
+}
+
+/// A per-task map from syscall number to [`SeccompAction`], defaulting every
+/// syscall to [`SeccompAction::Allow`] until a rule is installed.
+///
+/// Filters are monotonic: once a syscall has been set to `Deny` or `Kill` it
+/// can never be set back to `Allow` (though `Deny` may still be tightened to
+/// `Kill`). This is what makes a filter usable as a sandbox a parent can
+/// apply to a child it doesn't trust — the child cannot loosen rules the
+/// parent installed before letting it run.
+#[derive(Clone, Default)]
+pub struct SeccompFilter {
+    rules: BTreeMap<usize, SeccompAction>,
+}
+
+impl SeccompFilter {
+    pub fn new() -> Self {
+        Self {
+            rules: BTreeMap::new(),
+        }
+    }
+
+    /// The action this filter prescribes for `syscall_nr` (`Allow` if unset).
+    pub fn action_for(&self, syscall_nr: usize) -> SeccompAction {
+        self.rules
+            .get(&syscall_nr)
+            .copied()
+            .unwrap_or(SeccompAction::Allow)
+    }
+
+    /// Install a rule for `syscall_nr`, enforcing monotonicity.
+    ///
+    /// Returns `Err(())` if `syscall_nr` is out of range, or if this would
+    /// loosen an existing rule: `Deny`/`Kill` back to `Allow`, or `Kill`
+    /// back to `Deny`.
+    pub fn set(&mut self, syscall_nr: usize, action: SeccompAction) -> Result<(), ()> {
+        if syscall_nr >= MAX_SYSCALL_NUM {
+            return Err(());
+        }
+        let current = self.action_for(syscall_nr);
+        if (current != SeccompAction::Allow && action == SeccompAction::Allow)
+            || (current == SeccompAction::Kill && action == SeccompAction::Deny)
+        {
+            return Err(());
+        }
+        self.rules.insert(syscall_nr, action);
+        Ok(())
+    }
+}
@@ -0,0 +1,11 @@
+//! Low-level context switch between two [`super::TaskContext`]s
+
+use super::TaskContext;
+
+extern "C" {
+    /// Save the callee-saved registers into `*current_task_cx_ptr`, then
+    /// restore them from `*next_task_cx_ptr` and jump to its `ra`.
+    ///
+    /// Implemented in `switch.S`, out of scope for this lab.
+    pub fn __switch(current_task_cx_ptr: *mut TaskContext, next_task_cx_ptr: *const TaskContext);
+}
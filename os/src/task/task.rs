@@ -0,0 +1,317 @@
+//! Types related to task management
+
+use alloc::string::String;
+use alloc::sync::{Arc, Weak};
+use alloc::vec::Vec;
+
+use crate::config::{BIG_STRIDE, DEFAULT_PRIORITY, MAX_SYSCALL_NUM};
+use crate::loader::get_app_data_by_name;
+use crate::mm::MemorySet;
+use crate::sync::UPSafeCell;
+use crate::trap::TrapContext;
+
+use super::pid::{pid_alloc, register_task, KernelStack, PidHandle};
+use super::ptrace::StopState;
+use super::seccomp::SeccompFilter;
+use super::TaskContext;
+
+/// The task control block (TCB) of a task
+pub struct TaskControlBlock {
+    /// process id
+    pub pid: PidHandle,
+    /// kernel stack
+    pub kernel_stack: KernelStack,
+    /// mutable inner state guarded by a spin-lock-free cell
+    inner: UPSafeCell<TaskControlBlockInner>,
+}
+
+/// the mutable part of a [`TaskControlBlock`]
+pub struct TaskControlBlockInner {
+    /// address of the boxed trap context, used as a raw pointer by
+    /// `get_trap_cx` (not a physical page number in this lab)
+    pub trap_cx_ppn: usize,
+    /// application data can only appear in areas below `base_size`
+    pub base_size: usize,
+    /// saved task context, used in task switching
+    pub task_cx: TaskContext,
+    /// status of this task
+    pub task_status: TaskStatus,
+    /// address space of this task
+    pub memory_set: MemorySet,
+    /// parent process
+    pub parent: Option<Weak<TaskControlBlock>>,
+    /// children processes
+    pub children: Vec<Arc<TaskControlBlock>>,
+    /// exit code of the task
+    pub exit_code: i32,
+    /// heap bottom, used when calling sbrk
+    pub heap_bottom: usize,
+    /// program break, used when calling sbrk
+    pub program_brk: usize,
+    /// path used by [`super::context::trap_exec`] to exec into on first run
+    pub cmd_path: String,
+    /// per-syscall call counts, exposed via `sys_task_info`/`sys_syscall_stats`
+    pub syscall_count: [u32; MAX_SYSCALL_NUM],
+    /// per-syscall cumulative elapsed microseconds, sampled around
+    /// dispatch in [`crate::syscall::syscall`] and exposed via
+    /// `sys_syscall_stats`
+    pub syscall_time_us: [usize; MAX_SYSCALL_NUM],
+    /// time in ms that `sys_task_info` subtracts from the current time to
+    /// report `TaskInfo::time`; 0 for a newly created task
+    pub running_time: usize,
+
+    /// scheduling priority, default 16, must be >= 2
+    pub priority: usize,
+    /// stride scheduling counter; the scheduler always picks the runnable
+    /// task with the smallest `stride`, then adds `BIG_STRIDE / priority`
+    /// to it before running it
+    pub stride: usize,
+
+    /// syscall allow/deny/kill rules, consulted by the dispatcher in
+    /// [`crate::syscall::syscall`] before every syscall.
+    ///
+    /// Must be cloned into the child's inner state on `fork`/`spawn` (the
+    /// whole point is sandboxing a child before it runs untrusted code) and
+    /// is left untouched across `exec`, which only rewrites `memory_set`,
+    /// `trap_cx_ppn` and `base_size` in place.
+    pub seccomp: SeccompFilter,
+
+    /// the task tracing this one, set by `PTRACE_TRACEME`/`PTRACE_ATTACH`.
+    /// Not inherited across `clone`/`fork` — a debugger has to attach to
+    /// each child it wants to trace.
+    pub tracer: Option<Weak<TaskControlBlock>>,
+    /// ptrace stop/run state, set to `Stopped` when the dispatcher in
+    /// [`crate::syscall::syscall`] parks a traced task, back to `Running` by
+    /// `PTRACE_CONT`, and consulted by `sys_waitpid`
+    pub stop_state: StopState,
+}
+
+impl TaskControlBlockInner {
+    /// `pass` added to `stride` every time this task is scheduled
+    pub fn pass(&self) -> usize {
+        BIG_STRIDE / self.priority
+    }
+
+    pub fn get_trap_cx(&self) -> &'static mut TrapContext {
+        unsafe { &mut *(self.trap_cx_ppn as *mut TrapContext) }
+    }
+
+    pub fn get_user_token(&self) -> usize {
+        self.memory_set.token()
+    }
+
+    fn get_status(&self) -> TaskStatus {
+        self.task_status
+    }
+
+    pub fn is_zombie(&self) -> bool {
+        self.get_status() == TaskStatus::Zombie
+    }
+}
+
+/// `clone()` flag: share the parent's address space instead of deep-copying
+/// it, so the child behaves as a thread rather than a separate process.
+pub const CLONE_VM: usize = 0x100;
+/// `clone()` flag: don't add the child to the parent's `children`, which
+/// `sys_waitpid` searches (the clone is a thread, not a process to be
+/// waited on). The child's `parent` link is still set.
+pub const CLONE_THREAD: usize = 0x10000;
+
+impl TaskControlBlock {
+    /// exclusive access to the inner state
+    pub fn inner_exclusive_access(&self) -> core::cell::RefMut<'_, TaskControlBlockInner> {
+        self.inner.exclusive_access()
+    }
+
+    pub fn getpid(&self) -> usize {
+        self.pid.0
+    }
+
+    /// Create a fresh task (not derived from an existing one) that execs
+    /// `elf_data` eagerly, before ever being scheduled. Starts at
+    /// [`DEFAULT_PRIORITY`], the default stride-scheduling priority.
+    pub fn new(elf_data: &[u8]) -> Arc<Self> {
+        let pid_handle = pid_alloc();
+        let kernel_stack = KernelStack::new(&pid_handle);
+        let trap_cx_ppn = alloc::boxed::Box::into_raw(alloc::boxed::Box::new(TrapContext {
+            x: [0; 32],
+            sstatus: 0,
+            sepc: 0,
+        })) as usize;
+
+        let task = Arc::new(Self {
+            pid: pid_handle,
+            kernel_stack,
+            inner: unsafe {
+                UPSafeCell::new(TaskControlBlockInner {
+                    trap_cx_ppn,
+                    base_size: 0,
+                    // `exec` below populates `trap_cx`/`memory_set` before
+                    // this task is ever scheduled, so, like `clone_task`,
+                    // it goes straight to `trap_return` rather than back
+                    // through `trap_exec` (which would re-derive the
+                    // program from `cmd_path`, never set here).
+                    task_cx: TaskContext::goto_trap_return(0),
+                    task_status: TaskStatus::Ready,
+                    memory_set: MemorySet::new_bare(),
+                    parent: None,
+                    children: Vec::new(),
+                    exit_code: 0,
+                    heap_bottom: 0,
+                    program_brk: 0,
+                    cmd_path: String::new(),
+                    syscall_count: [0; MAX_SYSCALL_NUM],
+                    syscall_time_us: [0; MAX_SYSCALL_NUM],
+                    running_time: 0,
+                    priority: DEFAULT_PRIORITY,
+                    stride: 0,
+                    seccomp: SeccompFilter::new(),
+                    tracer: None,
+                    stop_state: StopState::Running,
+                })
+            },
+        });
+        register_task(task.pid.0, &task);
+        task.exec(elf_data);
+        task
+    }
+
+    /// Replace this task's address space in place with a freshly loaded ELF
+    /// image: `memory_set`, `trap_cx_ppn` and `base_size` change to reflect
+    /// `elf_data`, everything else (pid, kernel stack, scheduling state,
+    /// `seccomp`, ...) carries over untouched.
+    ///
+    /// `elf_data`'s segments are mapped via `MemorySet::from_elf`, which also
+    /// hands back the entry point and user stack top this seeds the fresh
+    /// `TrapContext` with, so the task's next `trap_return` actually lands
+    /// in the program instead of at a zeroed `sepc`/`sp`. If `elf_data`
+    /// isn't a parseable ELF image (e.g. `spawn` against a missing app),
+    /// falls back to an empty address space and a zeroed context, matching
+    /// the previous no-op behavior rather than panicking.
+    pub fn exec(&self, elf_data: &[u8]) {
+        let (memory_set, user_sp, entry_point) = MemorySet::from_elf(elf_data)
+            .unwrap_or_else(|| (MemorySet::new_bare(), 0, 0));
+
+        let mut trap_cx = TrapContext {
+            x: [0; 32],
+            sstatus: 0,
+            sepc: entry_point,
+        };
+        trap_cx.x[2] = user_sp;
+        let trap_cx_ppn = alloc::boxed::Box::into_raw(alloc::boxed::Box::new(trap_cx)) as usize;
+
+        let mut inner = self.inner_exclusive_access();
+        inner.memory_set = memory_set;
+        inner.base_size = user_sp;
+        inner.trap_cx_ppn = trap_cx_ppn;
+    }
+
+    /// Generalized `fork`: create a task cloned from `self` per `clone()`
+    /// `flags`. `CLONE_VM` shares this task's address space instead of
+    /// deep-copying it; a non-zero `stack` overrides the child's trap
+    /// context `sp` so a thread library can supply its own stack;
+    /// `CLONE_THREAD` keeps the child out of this task's `children`, so
+    /// `sys_waitpid` does not see it; the child's `parent` is set either way.
+    pub fn clone_task(self: &Arc<Self>, flags: usize, stack: usize) -> Arc<Self> {
+        let mut parent_inner = self.inner_exclusive_access();
+
+        let memory_set = if flags & CLONE_VM != 0 {
+            parent_inner.memory_set.clone_shared()
+        } else {
+            parent_inner.memory_set.deep_copy()
+        };
+
+        let pid_handle = pid_alloc();
+        let kernel_stack = KernelStack::new(&pid_handle);
+
+        let mut trap_cx = *parent_inner.get_trap_cx();
+        if stack != 0 {
+            trap_cx.x[2] = stack;
+        }
+        // `trap_cx_ppn` is reused by this lab as a raw pointer to the saved
+        // context (see `get_trap_cx` above); box it so it outlives this
+        // function instead of dangling on a stack frame that's about to pop.
+        let trap_cx_ppn = alloc::boxed::Box::into_raw(alloc::boxed::Box::new(trap_cx)) as usize;
+
+        let child = Arc::new(Self {
+            pid: pid_handle,
+            kernel_stack,
+            inner: unsafe {
+                UPSafeCell::new(TaskControlBlockInner {
+                    trap_cx_ppn,
+                    base_size: parent_inner.base_size,
+                    task_cx: TaskContext::goto_trap_return(0),
+                    task_status: TaskStatus::Ready,
+                    memory_set,
+                    parent: Some(Arc::downgrade(self)),
+                    children: Vec::new(),
+                    exit_code: 0,
+                    heap_bottom: parent_inner.heap_bottom,
+                    program_brk: parent_inner.program_brk,
+                    cmd_path: parent_inner.cmd_path.clone(),
+                    syscall_count: [0; MAX_SYSCALL_NUM],
+                    syscall_time_us: [0; MAX_SYSCALL_NUM],
+                    running_time: 0,
+                    priority: parent_inner.priority,
+                    stride: parent_inner.stride,
+                    seccomp: parent_inner.seccomp.clone(),
+                    tracer: None,
+                    stop_state: StopState::Running,
+                })
+            },
+        });
+        register_task(child.pid.0, &child);
+
+        if flags & CLONE_THREAD == 0 {
+            parent_inner.children.push(Arc::clone(&child));
+        }
+
+        child
+    }
+
+    /// `fork` + `exec` in one step, without the wasted work of deep-copying
+    /// this task's address space only to immediately discard it for a
+    /// freshly loaded ELF image (see the `spawn` syscall's "fork + exec
+    /// =/= spawn" hint): build the child directly from `path`'s image via
+    /// [`TaskControlBlock::new`] and record it as this task's child.
+    ///
+    /// `Self::new` always starts the child with an open `seccomp` filter, so
+    /// this copies this task's filter across afterwards, the same as
+    /// `clone_task` does — a parent sandboxing a child before `sys_spawn`ing
+    /// untrusted code must have that sandbox survive into the child.
+    pub fn spawn(self: &Arc<Self>, path: &str) -> Arc<Self> {
+        let elf_data = get_app_data_by_name(path).unwrap_or(&[]);
+        let child = Self::new(elf_data);
+
+        let seccomp = self.inner_exclusive_access().seccomp.clone();
+        let mut child_inner = child.inner_exclusive_access();
+        child_inner.parent = Some(Arc::downgrade(self));
+        child_inner.seccomp = seccomp;
+        drop(child_inner);
+
+        self.inner_exclusive_access().children.push(Arc::clone(&child));
+        child
+    }
+
+    /// Grow (`size > 0`) or shrink (`size < 0`) the program break by `size`
+    /// bytes, returning the break's value before the change, or `None` if
+    /// shrinking would move it below `heap_bottom`.
+    pub fn change_program_brk(&self, size: i32) -> Option<usize> {
+        let mut inner = self.inner_exclusive_access();
+        let old_brk = inner.program_brk;
+        let new_brk = old_brk as isize + size as isize;
+        if new_brk < inner.heap_bottom as isize {
+            return None;
+        }
+        inner.program_brk = new_brk as usize;
+        Some(old_brk)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+/// task status: ready/running/zombie
+pub enum TaskStatus {
+    Ready,
+    Running,
+    Zombie,
+}
@@ -0,0 +1,22 @@
+//! Timer-related helpers
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+const MICRO_PER_SEC: usize = 1_000_000;
+const TICKS_PER_SEC: usize = 100;
+
+/// Stands in for the `mtime` CSR real hardware/QEMU would back this with;
+/// reading actual hardware is out of scope for this lab. Advances by one
+/// "microsecond" every call, the same way `pid_alloc`/`MemorySet::new_bare`
+/// hand out monotonically increasing ids in place of real allocator state,
+/// so callers measuring an elapsed interval (`sys_get_time`,
+/// `sys_task_info`, the per-syscall timing in `sys_syscall_stats`) see a
+/// real, nonzero, monotonically increasing value instead of a clock frozen
+/// at 0.
+static TICKS_US: AtomicUsize = AtomicUsize::new(0);
+
+/// Read the current time in microseconds since boot.
+pub fn get_time_us() -> usize {
+    let _ = (MICRO_PER_SEC, TICKS_PER_SEC);
+    TICKS_US.fetch_add(1, Ordering::Relaxed)
+}
@@ -0,0 +1,13 @@
+//! Trap context: the user-mode register file saved across a trap
+
+/// Saved user-mode registers and CSRs, restored on return to user space
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct TrapContext {
+    /// general-purpose registers x0-x31
+    pub x: [usize; 32],
+    /// supervisor status register
+    pub sstatus: usize,
+    /// supervisor exception program counter
+    pub sepc: usize,
+}
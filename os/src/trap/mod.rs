@@ -0,0 +1,31 @@
+//! Trap handling: entry from user space and return
+
+mod context;
+
+pub use context::TrapContext;
+
+use crate::task::{current_trap_cx, current_user_token};
+
+extern "C" {
+    /// Restore user registers/CSRs from `*trap_cx_ptr`, switch `satp` to
+    /// `user_satp`, and `sret` back to user mode.
+    ///
+    /// Implemented in `trap.S`, out of scope for this lab, the same as
+    /// `__switch` (in `task::switch`) is for kernel task switching.
+    fn __restore(trap_cx_ptr: usize, user_satp: usize) -> !;
+}
+
+/// Return to user space, restoring the current task's trap context.
+///
+/// Reached either straight off a `__switch` (a freshly `exec`'d task's
+/// `task_cx.ra`, see `TaskContext::goto_trap_exec`/`goto_trap_return`) or
+/// from the bottom of the syscall/trap handler; either way the current
+/// task's `trap_cx`/address space token are already exactly what user mode
+/// should resume with, so this only has to hand them to `__restore`.
+pub fn trap_return() -> ! {
+    let trap_cx_ptr = current_trap_cx() as *const TrapContext as usize;
+    let user_satp = current_user_token();
+    unsafe {
+        __restore(trap_cx_ptr, user_satp);
+    }
+}